@@ -1,8 +1,253 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
+use ink::prelude::{string::String, vec::Vec};
+use ink::primitives::AccountId;
+
+pub type Balance = <ink::env::DefaultEnvironment as ink::env::Environment>::Balance;
+
+/// Errors defined by the PSP22 standard.
+#[derive(Debug, PartialEq, Eq, scale::Decode, scale::Encode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum PSP22Error {
+    /// Custom error type for implementation-based errors.
+    Custom(String),
+    /// Returned when an account does not have enough tokens to complete the operation.
+    InsufficientBalance,
+    /// Returned if there is not enough allowance to complete the operation.
+    InsufficientAllowance,
+    /// Returned if recipient's address is zero.
+    ZeroRecipientAddress,
+    /// Returned if sender's address is zero.
+    ZeroSenderAddress,
+    /// Returned if a safe transfer check failed.
+    SafeTransferCheckFailed(String),
+}
+
+/// The PSP22 fungible token interface.
+///
+/// The trait namespace gives every message its standard selector,
+/// e.g. `PSP22::transfer` is `0xdb20f9f5`.
+#[ink::trait_definition]
+pub trait PSP22 {
+    #[ink(message)]
+    fn total_supply(&self) -> Balance;
+
+    #[ink(message)]
+    fn balance_of(&self, owner: AccountId) -> Balance;
+
+    #[ink(message)]
+    fn allowance(&self, owner: AccountId, spender: AccountId) -> Balance;
+
+    #[ink(message)]
+    fn transfer(&mut self, to: AccountId, value: Balance, data: Vec<u8>) -> Result<(), PSP22Error>;
+
+    #[ink(message)]
+    fn transfer_from(
+        &mut self,
+        from: AccountId,
+        to: AccountId,
+        value: Balance,
+        data: Vec<u8>,
+    ) -> Result<(), PSP22Error>;
+
+    #[ink(message)]
+    fn approve(&mut self, spender: AccountId, value: Balance) -> Result<(), PSP22Error>;
+
+    #[ink(message)]
+    fn increase_allowance(
+        &mut self,
+        spender: AccountId,
+        delta_value: Balance,
+    ) -> Result<(), PSP22Error>;
+
+    #[ink(message)]
+    fn decrease_allowance(
+        &mut self,
+        spender: AccountId,
+        delta_value: Balance,
+    ) -> Result<(), PSP22Error>;
+}
+
+/// The PSP22 metadata extension.
+#[ink::trait_definition]
+pub trait PSP22Metadata {
+    #[ink(message)]
+    fn token_name(&self) -> Option<String>;
+
+    #[ink(message)]
+    fn token_symbol(&self) -> Option<String>;
+
+    #[ink(message)]
+    fn token_decimals(&self) -> u8;
+}
+
+/// Errors a PSP22 receiver returns to reject incoming tokens.
+#[derive(Debug, PartialEq, Eq, scale::Decode, scale::Encode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum PSP22ReceiverError {
+    TransferRejected(String),
+}
+
+/// Implemented by contracts that accept PSP22 tokens.
+///
+/// The token calls `before_received` on a contract recipient and reverts
+/// the transfer if the hook is missing or returns an error.
+#[ink::trait_definition]
+pub trait PSP22Receiver {
+    #[ink(message)]
+    fn before_received(
+        &mut self,
+        operator: AccountId,
+        from: AccountId,
+        value: Balance,
+        data: Vec<u8>,
+    ) -> Result<(), PSP22ReceiverError>;
+}
+
+/// Implemented by contracts that accept tokens through `transfer_and_call`.
+///
+/// Must return the selector of `ERC1363Receiver::on_transfer_received`.
+#[ink::trait_definition]
+pub trait ERC1363Receiver {
+    #[ink(message)]
+    fn on_transfer_received(
+        &mut self,
+        operator: AccountId,
+        from: AccountId,
+        value: Balance,
+        data: Vec<u8>,
+    ) -> [u8; 4];
+}
+
+/// Implemented by contracts that accept approvals through `approve_and_call`.
+///
+/// Must return the selector of `ERC1363Spender::on_approval_received`.
+#[ink::trait_definition]
+pub trait ERC1363Spender {
+    #[ink(message)]
+    fn on_approval_received(&mut self, owner: AccountId, value: Balance, data: Vec<u8>) -> [u8; 4];
+}
+
+/// Implemented by contracts that borrow through `flash_loan`.
+///
+/// Must return the selector of `ERC3156FlashBorrower::on_flash_loan` and
+/// approve the token to pull back `amount + fee`.
+#[ink::trait_definition]
+pub trait ERC3156FlashBorrower {
+    #[ink(message)]
+    fn on_flash_loan(
+        &mut self,
+        initiator: AccountId,
+        token: AccountId,
+        amount: Balance,
+        fee: Balance,
+        data: Vec<u8>,
+    ) -> [u8; 4];
+}
+
 #[ink::contract]
 mod wasmerc20 {
-    use ink_storage::Mapping;
+    use crate::{PSP22Error, PSP22Metadata, PSP22ReceiverError, PSP22};
+    use ink::env::call::{build_call, ExecutionInput, Selector};
+    use ink::env::CallFlags;
+    use ink::env::hash::Blake2x256;
+    use ink::prelude::{format, string::String, vec::Vec};
+    use ink_storage::{Lazy, Mapping};
+
+    pub type RoleId = u32;
+    pub type SnapshotId = u32;
+    /// `(snapshot id, value before the first change after that snapshot)` by index, ordered
+    /// by id; the `None` key tracks the total supply.
+    type Snapshots = Mapping<(Option<AccountId>, u32), (SnapshotId, Balance)>;
+    /// `(block number, value from that block on)` by index, ordered by block number; the
+    /// `None` key tracks the total supply.
+    type Checkpoints = Mapping<(Option<AccountId>, u32), (BlockNumber, Balance)>;
+    /// `(unlock timestamp, locked amount)` entries of one account.
+    type Locks = Vec<(Timestamp, Balance)>;
+    pub type CampaignId = u32;
+    pub type VestingId = u32;
+
+    /// Administers every role unless `set_role_admin` says otherwise.
+    pub const ADMIN: RoleId = 0;
+    pub const MINTER: RoleId = ink::selector_id!("MINTER");
+    pub const BURNER: RoleId = ink::selector_id!("BURNER");
+    pub const PAUSER: RoleId = ink::selector_id!("PAUSER");
+    pub const SNAPSHOT: RoleId = ink::selector_id!("SNAPSHOT");
+    pub const COMPLIANCE: RoleId = ink::selector_id!("COMPLIANCE");
+    pub const CLAWBACK: RoleId = ink::selector_id!("CLAWBACK");
+    pub const KYC_OFFICER: RoleId = ink::selector_id!("KYC_OFFICER");
+    /// Roles granted to the deployer, KYC_OFFICER only when permissioned; they move along
+    /// with ownership.
+    const OWNER_ROLES: [RoleId; 7] =
+        [ADMIN, MINTER, BURNER, PAUSER, SNAPSHOT, COMPLIANCE, KYC_OFFICER];
+
+    /// ERC-1404 transfer restriction codes.
+    pub const TRANSFER_OK: u8 = 0;
+    pub const RESTRICTION_PAUSED: u8 = 1;
+    pub const RESTRICTION_ACCOUNT_FROZEN: u8 = 2;
+    pub const RESTRICTION_INSUFFICIENT_BALANCE: u8 = 3;
+    pub const RESTRICTION_BALANCE_LOCKED: u8 = 4;
+    pub const RESTRICTION_NOT_ALLOWLISTED: u8 = 5;
+    pub const RESTRICTION_UNKNOWN: u8 = u8::MAX;
+
+    /// Fees are expressed in basis points of this denominator.
+    pub const MAX_BPS: u16 = 10_000;
+    /// Unexpired locks one account can hold; locks sharing an unlock time count once.
+    pub const MAX_LOCKS: usize = 32;
+
+    /// Deployment options of `new_with_config`; the default is an uncapped, unrestricted token.
+    #[derive(Debug, Clone, Default, PartialEq, Eq, scale::Decode, scale::Encode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub struct TokenConfig {
+        pub name: Option<String>,
+        pub symbol: Option<String>,
+        pub decimals: u8,
+        /// Bounds `total_supply` for the lifetime of the token.
+        pub cap: Option<Balance>,
+        /// `false` disables `clawback` for good.
+        pub clawback_enabled: bool,
+        /// Only lets allowlisted accounts receive tokens.
+        pub permissioned: bool,
+        /// The highest transfer fee an admin can ever set.
+        pub max_transfer_fee_bps: u16,
+    }
+
+    /// A Merkle airdrop funded from an admin's balance.
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Decode, scale::Encode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct AirdropCampaign {
+        /// Root over `blake2x256((index, account, amount))` leaves, hashing sorted pairs.
+        pub merkle_root: [u8; 32],
+        /// Tokens still held by the contract for this campaign.
+        pub remaining: Balance,
+        /// Claims are rejected and `sweep` is allowed from this timestamp on.
+        pub expires_at: Timestamp,
+    }
+
+    /// Tokens escrowed by the contract and released linearly to a beneficiary.
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Decode, scale::Encode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct VestingSchedule {
+        /// The account that funded the schedule and may revoke it.
+        pub creator: AccountId,
+        pub beneficiary: AccountId,
+        /// Total tokens of the schedule; cut down to the vested part on revocation.
+        pub amount: Balance,
+        pub released: Balance,
+        pub start: Timestamp,
+        /// Time after `start` before anything vests.
+        pub cliff: Timestamp,
+        /// Time after `start` until everything has vested.
+        pub duration: Timestamp,
+        pub revocable: bool,
+        pub revoked: bool,
+    }
 
     /// Defines the storage of your contract.
     /// Add new fields to the below struct in order
@@ -10,184 +255,2143 @@ mod wasmerc20 {
     #[ink(storage)]
     pub struct Wasmerc20 {
         total_supply: Balance,
+        cap: Option<Balance>,
         balances: Mapping<AccountId, Balance>,
         approval: Mapping<(AccountId, AccountId), Balance>,
-        owner: AccountId,
+        owner: Option<AccountId>,
+        pending_owner: Option<AccountId>,
+        roles: Mapping<(RoleId, AccountId), ()>,
+        role_admins: Mapping<RoleId, RoleId>,
+        paused: bool,
+        snapshot_id: SnapshotId,
+        snapshots: Snapshots,
+        snapshot_counts: Mapping<Option<AccountId>, u32>,
+        delegates: Mapping<AccountId, AccountId>,
+        checkpoints: Checkpoints,
+        checkpoint_counts: Mapping<Option<AccountId>, u32>,
+        nonces: Mapping<AccountId, u64>,
+        flash_fee_bps: u16,
+        flash_fee_receiver: Option<AccountId>,
+        /// Set while a callback of `_call_and_check` runs; kept out of the root cell so
+        /// reentrant calls see it.
+        callback_active: Lazy<bool>,
+        next_campaign_id: CampaignId,
+        campaigns: Mapping<CampaignId, AirdropCampaign>,
+        claimed_bitmap: Mapping<(CampaignId, u32), u128>,
+        next_vesting_id: VestingId,
+        vesting_schedules: Mapping<VestingId, VestingSchedule>,
+        locks: Mapping<AccountId, Locks>,
+        frozen: Mapping<AccountId, ()>,
+        clawback_enabled: bool,
+        permissioned: bool,
+        allowlist: Mapping<AccountId, ()>,
+        transfer_fee_bps: u16,
+        max_transfer_fee_bps: u16,
+        fee_treasury: Option<AccountId>,
+        fee_exempt: Mapping<AccountId, ()>,
+        name: Option<String>,
+        symbol: Option<String>,
+        decimals: u8,
     }
 
     #[ink(event)]
     pub struct Transfer {
         #[ink(topic)]
         from: Option<AccountId>,
+        #[ink(topic)]
         to: Option<AccountId>,
         value: Balance,
     }
 
     #[ink(event)]
     pub struct Approval {
+        #[ink(topic)]
         owner: AccountId,
+        #[ink(topic)]
         spender: AccountId,
         value: Balance,
     }
 
+    #[ink(event)]
+    pub struct OwnershipTransferred {
+        #[ink(topic)]
+        previous_owner: Option<AccountId>,
+        #[ink(topic)]
+        new_owner: Option<AccountId>,
+    }
+
+    #[ink(event)]
+    pub struct RoleGranted {
+        #[ink(topic)]
+        role: RoleId,
+        #[ink(topic)]
+        grantee: AccountId,
+        #[ink(topic)]
+        grantor: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct RoleRevoked {
+        #[ink(topic)]
+        role: RoleId,
+        #[ink(topic)]
+        account: AccountId,
+        #[ink(topic)]
+        sender: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct RoleAdminChanged {
+        #[ink(topic)]
+        role: RoleId,
+        previous_admin_role: RoleId,
+        new_admin_role: RoleId,
+    }
+
+    #[ink(event)]
+    pub struct Paused {
+        account: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct Unpaused {
+        account: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct Snapshot {
+        id: SnapshotId,
+    }
+
+    #[ink(event)]
+    pub struct DelegateChanged {
+        #[ink(topic)]
+        delegator: AccountId,
+        #[ink(topic)]
+        from_delegate: Option<AccountId>,
+        #[ink(topic)]
+        to_delegate: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct DelegateVotesChanged {
+        #[ink(topic)]
+        delegate: AccountId,
+        previous_balance: Balance,
+        new_balance: Balance,
+    }
+
+    #[ink(event)]
+    pub struct AirdropCreated {
+        #[ink(topic)]
+        campaign_id: CampaignId,
+        merkle_root: [u8; 32],
+        amount: Balance,
+        expires_at: Timestamp,
+    }
+
+    #[ink(event)]
+    pub struct AirdropClaimed {
+        #[ink(topic)]
+        campaign_id: CampaignId,
+        #[ink(topic)]
+        account: AccountId,
+        index: u32,
+        amount: Balance,
+    }
+
+    #[ink(event)]
+    pub struct AirdropSwept {
+        #[ink(topic)]
+        campaign_id: CampaignId,
+        amount: Balance,
+    }
+
+    #[ink(event)]
+    pub struct VestingCreated {
+        #[ink(topic)]
+        schedule_id: VestingId,
+        #[ink(topic)]
+        beneficiary: AccountId,
+        amount: Balance,
+    }
+
+    #[ink(event)]
+    pub struct VestingReleased {
+        #[ink(topic)]
+        schedule_id: VestingId,
+        #[ink(topic)]
+        beneficiary: AccountId,
+        amount: Balance,
+    }
+
+    #[ink(event)]
+    pub struct VestingRevoked {
+        #[ink(topic)]
+        schedule_id: VestingId,
+        refund: Balance,
+    }
+
+    #[ink(event)]
+    pub struct TokensLocked {
+        #[ink(topic)]
+        account: AccountId,
+        amount: Balance,
+        unlock_at: Timestamp,
+    }
+
+    #[ink(event)]
+    pub struct AccountFrozen {
+        #[ink(topic)]
+        account: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct AccountUnfrozen {
+        #[ink(topic)]
+        account: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct Clawback {
+        #[ink(topic)]
+        from: AccountId,
+        #[ink(topic)]
+        to: AccountId,
+        value: Balance,
+        reason_hash: [u8; 32],
+    }
+
+    #[ink(event)]
+    pub struct AllowlistAdded {
+        #[ink(topic)]
+        account: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct AllowlistRemoved {
+        #[ink(topic)]
+        account: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct FeeCharged {
+        #[ink(topic)]
+        from: AccountId,
+        #[ink(topic)]
+        treasury: Option<AccountId>,
+        fee: Balance,
+    }
+
     #[derive(Debug, PartialEq, Eq, scale::Decode, scale::Encode)]
     #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
     pub enum Error {
         InsufficientBalance,
         InsufficientApproval,
         IllegalManager,
+        AllowanceBelowZero,
+        Overflow,
+        Underflow,
+        MissingRole(RoleId),
+        Paused,
+        NotPaused,
+        CapExceeded,
+        NonexistentSnapshot,
+        FutureLookup,
+        PermitExpired,
+        InvalidSignature,
+        SafeTransferCheckFailed(String),
+        CallbackFailed(String),
+        MaxFlashLoanExceeded,
+        InvalidFee,
+        NonexistentCampaign,
+        CampaignExpired,
+        CampaignNotExpired,
+        AlreadyClaimed,
+        InvalidProof,
+        NonexistentVesting,
+        InvalidSchedule,
+        NotRevocable,
+        BalanceLocked,
+        AccountFrozen,
+        ClawbackDisabled,
+        TransferRestricted(u8),
+        NotAllowlisted,
+        ReentrantCall,
+        TooManyLocks,
+    }
+
+    /// `TransferRestricted(RESTRICTION_INSUFFICIENT_BALANCE)` surfaces as `InsufficientBalance`;
+    /// other codes as
+    /// `Custom("TransferRestricted(<code>): <message_for_transfer_restriction(code)>")`.
+    impl From<Error> for PSP22Error {
+        fn from(error: Error) -> Self {
+            match error {
+                Error::InsufficientBalance => PSP22Error::InsufficientBalance,
+                Error::InsufficientApproval | Error::AllowanceBelowZero => {
+                    PSP22Error::InsufficientAllowance
+                }
+                Error::SafeTransferCheckFailed(reason) => {
+                    PSP22Error::SafeTransferCheckFailed(reason)
+                }
+                Error::TransferRestricted(RESTRICTION_INSUFFICIENT_BALANCE) => {
+                    PSP22Error::InsufficientBalance
+                }
+                Error::TransferRestricted(code) => PSP22Error::Custom(format!(
+                    "TransferRestricted({}): {}",
+                    code,
+                    Wasmerc20::_restriction_message(code)
+                )),
+                other => PSP22Error::Custom(format!("{:?}", other)),
+            }
+        }
     }
 
     impl Wasmerc20 {
         /// Constructor that initializes.
         #[ink(constructor)]
         pub fn new(total_supply: Balance) -> Self {
+            Self::new_with_config(total_supply, TokenConfig::default())
+        }
+
+        /// Constructor that initializes with token name, symbol and decimals.
+        #[ink(constructor)]
+        pub fn new_with_metadata(
+            total_supply: Balance,
+            name: Option<String>,
+            symbol: Option<String>,
+            decimals: u8,
+        ) -> Self {
+            Self::new_with_config(
+                total_supply,
+                TokenConfig {
+                    name,
+                    symbol,
+                    decimals,
+                    ..Default::default()
+                },
+            )
+        }
+
+        /// Constructor that initializes with the given `TokenConfig`.
+        #[ink(constructor)]
+        pub fn new_with_config(total_supply: Balance, config: TokenConfig) -> Self {
+            let TokenConfig {
+                name,
+                symbol,
+                decimals,
+                cap,
+                clawback_enabled,
+                permissioned,
+                max_transfer_fee_bps,
+            } = config;
+            if let Some(cap) = cap {
+                assert!(total_supply <= cap, "initial supply exceeds the cap");
+            }
+            assert!(max_transfer_fee_bps <= MAX_BPS, "maximum transfer fee exceeds 100%");
+
             let mut balances = Mapping::default();
             let sender = Self::env().caller();
-            balances.insert(&sender, &total_supply);
+            balances.insert(sender, &total_supply);
 
             Self::env().emit_event(Transfer {
                 from: None,
                 to: Some(sender),
                 value: total_supply,
             });
+            Self::env().emit_event(OwnershipTransferred {
+                previous_owner: None,
+                new_owner: Some(sender),
+            });
 
-            Self {
+            let mut instance = Self {
                 total_supply,
+                cap,
                 balances,
                 approval: Default::default(),
-                owner: sender,
+                owner: Some(sender),
+                pending_owner: None,
+                roles: Default::default(),
+                role_admins: Default::default(),
+                paused: false,
+                snapshot_id: 0,
+                snapshots: Default::default(),
+                snapshot_counts: Default::default(),
+                delegates: Default::default(),
+                checkpoints: Default::default(),
+                checkpoint_counts: Default::default(),
+                nonces: Default::default(),
+                flash_fee_bps: 0,
+                flash_fee_receiver: None,
+                callback_active: Default::default(),
+                next_campaign_id: 0,
+                campaigns: Default::default(),
+                claimed_bitmap: Default::default(),
+                next_vesting_id: 0,
+                vesting_schedules: Default::default(),
+                locks: Default::default(),
+                frozen: Default::default(),
+                clawback_enabled,
+                permissioned,
+                allowlist: Default::default(),
+                transfer_fee_bps: 0,
+                max_transfer_fee_bps,
+                fee_treasury: None,
+                fee_exempt: Default::default(),
+                name,
+                symbol,
+                decimals,
+            };
+            for role in OWNER_ROLES {
+                if role == KYC_OFFICER && !permissioned {
+                    continue;
+                }
+                instance._grant_role(role, sender);
+            }
+            instance._write_total_supply_checkpoint();
+            instance.fee_exempt.insert(Self::env().account_id(), &());
+            if permissioned {
+                instance._set_allowlisted(sender, true);
+                instance._set_allowlisted(Self::env().account_id(), true);
             }
+
+            instance
         }
 
         #[ink(message)]
-        pub fn owner(&self) -> AccountId {
+        pub fn owner(&self) -> Option<AccountId> {
             self.owner
         }
 
+        /// The maximum `total_supply`, if the token is capped.
         #[ink(message)]
-        pub fn total_supply(&self) -> Balance {
-            self.total_supply
+        pub fn cap(&self) -> Option<Balance> {
+            self.cap
         }
 
         #[ink(message)]
-        pub fn balance_of(&self, who: AccountId) -> Balance {
-            self.balances.get(&who).unwrap_or_default()
+        pub fn pending_owner(&self) -> Option<AccountId> {
+            self.pending_owner
         }
 
+        /// Starts an ownership transfer; `new_owner` has to accept it.
         #[ink(message)]
-        pub fn approval(&self, owner: AccountId, spender: AccountId) -> Balance {
-            self.approval.get(&(owner, spender)).unwrap_or_default()
+        pub fn transfer_ownership(&mut self, new_owner: AccountId) -> Result<(), Error> {
+            self._check_not_reentrant()?;
+            self._check_owner()?;
+            self.pending_owner = Some(new_owner);
+
+            Ok(())
         }
 
         #[ink(message)]
-        pub fn transfer(
-            &mut self,
-            to: AccountId,
-            value: Balance,
-        ) -> core::result::Result<(), Error> {
-            let from = self.env().caller();
-            let from_balance = self.balance_of(from);
-            if from_balance < value {
-                return Err(Error::InsufficientBalance);
+        pub fn accept_ownership(&mut self) -> Result<(), Error> {
+            self._check_not_reentrant()?;
+            let caller = self.env().caller();
+            if self.pending_owner != Some(caller) {
+                return Err(Error::IllegalManager);
             }
 
-            self._transfer(Some(from), Some(to), value)
+            self._set_owner(Some(caller));
+            Ok(())
         }
 
+        /// Leaves the contract without an owner, revoking the roles held by the owner.
         #[ink(message)]
-        pub fn transfer_from(
-            &mut self,
-            from: AccountId,
-            to: AccountId,
-            value: Balance,
-        ) -> Result<(), Error> {
-            let caller = self.env().caller();
-            let approval = self.approval(from, caller);
-            if approval < value {
-                return Err(Error::InsufficientApproval);
-            }
+        pub fn renounce_ownership(&mut self) -> Result<(), Error> {
+            self._check_not_reentrant()?;
+            self._check_owner()?;
+            self._set_owner(None);
 
-            let from_balance = self.balance_of(from);
-            if from_balance < value {
-                return Err(Error::InsufficientBalance);
-            }
+            Ok(())
+        }
 
-            self.approval.insert((from, caller), &(approval - value));
-            self._transfer(Some(from), Some(to), value)
+        #[ink(message)]
+        pub fn has_role(&self, role: RoleId, account: AccountId) -> bool {
+            self.roles.contains((role, account))
         }
 
         #[ink(message)]
-        pub fn approve(&mut self, to: AccountId, value: Balance) -> Result<(), Error> {
-            let owner = self.env().caller();
-            self.approval.insert((owner, to), &value);
+        pub fn get_role_admin(&self, role: RoleId) -> RoleId {
+            self.role_admins.get(role).unwrap_or(ADMIN)
+        }
 
-            self.env().emit_event(Approval {
-                owner,
-                spender: to,
-                value,
-            });
+        #[ink(message)]
+        pub fn grant_role(&mut self, role: RoleId, account: AccountId) -> Result<(), Error> {
+            self._check_role(self.get_role_admin(role))?;
+            self._grant_role(role, account);
 
             Ok(())
         }
 
         #[ink(message)]
-        pub fn mint(
-            &mut self,
-            value: Balance,
-        ) -> core::result::Result<(), Error> {
-            let caller = self.env().caller();
-            if caller != self.owner {
-                return Err(Error::IllegalManager);
-            }
+        pub fn revoke_role(&mut self, role: RoleId, account: AccountId) -> Result<(), Error> {
+            self._check_role(self.get_role_admin(role))?;
+            self._revoke_role(role, account);
 
-            self.total_supply += value;
-            self._transfer(None, Some(caller), value)
+            Ok(())
         }
 
+        /// Gives up `role` held by the caller; `account` must be the caller.
         #[ink(message)]
-        pub fn burn(
-            &mut self,
-            value: Balance,
-        ) -> core::result::Result<(), Error> {
-            let caller = self.env().caller();
-            if caller != self.owner {
+        pub fn renounce_role(&mut self, role: RoleId, account: AccountId) -> Result<(), Error> {
+            if account != self.env().caller() {
                 return Err(Error::IllegalManager);
             }
+            self._check_role(role)?;
+            self._revoke_role(role, account);
 
-            let caller_balance = self.balance_of(caller);
-            if caller_balance < value {
-                return Err(Error::InsufficientBalance);
-            }
-
-            self.total_supply -= value;
-            self._transfer(Some(caller), None, value)
+            Ok(())
         }
 
-        pub fn _transfer(
+        #[ink(message)]
+        pub fn set_role_admin(
             &mut self,
-            from: Option<AccountId>,
-            to: Option<AccountId>,
-            value: Balance,
+            role: RoleId,
+            new_admin_role: RoleId,
         ) -> Result<(), Error> {
-            if from.is_some() {
-                let from_balance = self.balance_of(from.unwrap());
-                self.balances.insert(&from.unwrap(), &(from_balance - value));
+            let previous_admin_role = self.get_role_admin(role);
+            self._check_role(previous_admin_role)?;
+            self.role_admins.insert(role, &new_admin_role);
+
+            self.env().emit_event(RoleAdminChanged {
+                role,
+                previous_admin_role,
+                new_admin_role,
+            });
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn is_paused(&self) -> bool {
+            self.paused
+        }
+
+        /// Stops all token movements until `unpause` is called.
+        #[ink(message)]
+        pub fn pause(&mut self) -> Result<(), Error> {
+            self._check_not_reentrant()?;
+            self._check_owner_or_role(PAUSER)?;
+            if self.paused {
+                return Err(Error::Paused);
             }
-            
-            if to.is_some() {
-                let to_balance = self.balance_of(to.unwrap());
-                self.balances.insert(&to.unwrap(), &(to_balance + value));
+            self.paused = true;
+
+            self.env().emit_event(Paused {
+                account: self.env().caller(),
+            });
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn unpause(&mut self) -> Result<(), Error> {
+            self._check_not_reentrant()?;
+            self._check_owner_or_role(PAUSER)?;
+            if !self.paused {
+                return Err(Error::NotPaused);
             }
-            
-            self.env().emit_event(Transfer {
-                from,
-                to,
-                value,
+            self.paused = false;
+
+            self.env().emit_event(Unpaused {
+                account: self.env().caller(),
             });
 
             Ok(())
         }
-    }
 
+        /// Records the current balances and supply under a new, incrementing id.
+        #[ink(message)]
+        pub fn snapshot(&mut self) -> Result<SnapshotId, Error> {
+            self._check_not_reentrant()?;
+            self._check_role(SNAPSHOT)?;
+            self.snapshot_id = self.snapshot_id.checked_add(1).ok_or(Error::Overflow)?;
+
+            self.env().emit_event(Snapshot {
+                id: self.snapshot_id,
+            });
+
+            Ok(self.snapshot_id)
+        }
+
+        #[ink(message)]
+        pub fn balance_of_at(
+            &self,
+            who: AccountId,
+            snapshot_id: SnapshotId,
+        ) -> Result<Balance, Error> {
+            Ok(self
+                ._value_at(Some(who), snapshot_id)?
+                .unwrap_or_else(|| self._balance_of(who)))
+        }
+
+        #[ink(message)]
+        pub fn total_supply_at(&self, snapshot_id: SnapshotId) -> Result<Balance, Error> {
+            Ok(self
+                ._value_at(None, snapshot_id)?
+                .unwrap_or(self.total_supply))
+        }
+
+        #[ink(message)]
+        pub fn delegates(&self, account: AccountId) -> Option<AccountId> {
+            self.delegates.get(account)
+        }
+
+        /// Hands the caller's voting power to `delegatee`; delegate to yourself to vote directly.
+        #[ink(message)]
+        pub fn delegate(&mut self, delegatee: AccountId) -> Result<(), Error> {
+            let delegator = self.env().caller();
+            let from_delegate = self.delegates(delegator);
+            self.delegates.insert(delegator, &delegatee);
+
+            self.env().emit_event(DelegateChanged {
+                delegator,
+                from_delegate,
+                to_delegate: delegatee,
+            });
+
+            self._move_voting_power(from_delegate, Some(delegatee), self._balance_of(delegator))
+        }
+
+        #[ink(message)]
+        pub fn get_votes(&self, account: AccountId) -> Balance {
+            let len = self.checkpoint_counts.get(Some(account)).unwrap_or_default();
+
+            len.checked_sub(1)
+                .map(|last| self._checkpoint(Some(account), last).1)
+                .unwrap_or_default()
+        }
+
+        /// Voting power of `account` at the end of a past `block_number`.
+        #[ink(message)]
+        pub fn get_past_votes(
+            &self,
+            account: AccountId,
+            block_number: BlockNumber,
+        ) -> Result<Balance, Error> {
+            self._checkpoint_at(Some(account), block_number)
+        }
+
+        #[ink(message)]
+        pub fn get_past_total_supply(&self, block_number: BlockNumber) -> Result<Balance, Error> {
+            self._checkpoint_at(None, block_number)
+        }
+
+        #[ink(message)]
+        pub fn nonces(&self, owner: AccountId) -> u64 {
+            self.nonces.get(owner).unwrap_or_default()
+        }
+
+        /// Binds permit signatures to this token contract.
+        #[ink(message)]
+        pub fn domain_separator(&self) -> [u8; 32] {
+            self.env().hash_encoded::<Blake2x256, _>(&(
+                b"PSP22Permit",
+                &self.name,
+                self.env().account_id(),
+            ))
+        }
+
+        /// Approves `spender` on behalf of `owner` from an off-chain ECDSA signature.
+        ///
+        /// `owner` signs the Blake2x256 hash of the SCALE-encoded
+        /// `(domain_separator, owner, spender, value, nonce, deadline)`; the
+        /// owner's account id is the Blake2x256 hash of the compressed public key.
+        #[ink(message)]
+        pub fn permit(
+            &mut self,
+            owner: AccountId,
+            spender: AccountId,
+            value: Balance,
+            deadline: Timestamp,
+            signature: [u8; 65],
+        ) -> Result<(), Error> {
+            if self.env().block_timestamp() > deadline {
+                return Err(Error::PermitExpired);
+            }
+
+            let nonce = self.nonces(owner);
+            let message_hash = self.env().hash_encoded::<Blake2x256, _>(&(
+                self.domain_separator(),
+                owner,
+                spender,
+                value,
+                nonce,
+                deadline,
+            ));
+            let public_key = self
+                .env()
+                .ecdsa_recover(&signature, &message_hash)
+                .map_err(|_| Error::InvalidSignature)?;
+            let signer = AccountId::from(self.env().hash_bytes::<Blake2x256>(&public_key));
+            if signer != owner {
+                return Err(Error::InvalidSignature);
+            }
+
+            self.nonces.insert(owner, &(nonce.checked_add(1).ok_or(Error::Overflow)?));
+            self._approve(owner, spender, value)?;
+
+            Ok(())
+        }
+
+        /// Transfers to a contract and notifies it through `ERC1363Receiver` in the same call.
+        #[ink(message)]
+        pub fn transfer_and_call(
+            &mut self,
+            to: AccountId,
+            value: Balance,
+            data: Vec<u8>,
+        ) -> Result<(), Error> {
+            let from = self.env().caller();
+            let received = value - self._transfer_fee(Some(from), Some(to), value);
+            self._transfer(Some(from), Some(to), value)?;
+
+            let selector = ink::selector_bytes!("ERC1363Receiver::on_transfer_received");
+            self._call_and_check(
+                to,
+                selector,
+                ExecutionInput::new(Selector::new(selector))
+                    .push_arg(from)
+                    .push_arg(from)
+                    .push_arg(received)
+                    .push_arg(data),
+            )
+        }
+
+        /// Approves a contract and notifies it through `ERC1363Spender` in the same call.
+        #[ink(message)]
+        pub fn approve_and_call(
+            &mut self,
+            spender: AccountId,
+            value: Balance,
+            data: Vec<u8>,
+        ) -> Result<(), Error> {
+            let owner = self.env().caller();
+            self._approve(owner, spender, value)?;
+
+            let selector = ink::selector_bytes!("ERC1363Spender::on_approval_received");
+            self._call_and_check(
+                spender,
+                selector,
+                ExecutionInput::new(Selector::new(selector))
+                    .push_arg(owner)
+                    .push_arg(value)
+                    .push_arg(data),
+            )
+        }
+
+        /// The largest amount `flash_loan` can mint without hitting the cap.
+        #[ink(message)]
+        pub fn max_flash_loan(&self) -> Balance {
+            self.cap.unwrap_or(Balance::MAX).saturating_sub(self.total_supply)
+        }
+
+        #[ink(message)]
+        pub fn flash_fee(&self, amount: Balance) -> Balance {
+            Self::_bps_of(amount, self.flash_fee_bps)
+        }
+
+        #[ink(message)]
+        pub fn flash_fee_receiver(&self) -> Option<AccountId> {
+            self.flash_fee_receiver
+        }
+
+        /// Sets the flash loan fee; without a receiver the fee is burned.
+        #[ink(message)]
+        pub fn set_flash_fee(
+            &mut self,
+            fee_bps: u16,
+            fee_receiver: Option<AccountId>,
+        ) -> Result<(), Error> {
+            self._check_not_reentrant()?;
+            self._check_role(ADMIN)?;
+            if fee_bps > MAX_BPS {
+                return Err(Error::InvalidFee);
+            }
+
+            self.flash_fee_bps = fee_bps;
+            self.flash_fee_receiver = fee_receiver;
+
+            Ok(())
+        }
+
+        /// Mints `amount` to `receiver`, calls `ERC3156FlashBorrower::on_flash_loan`
+        /// and then pulls back and burns `amount` plus `flash_fee(amount)`.
+        #[ink(message)]
+        pub fn flash_loan(
+            &mut self,
+            receiver: AccountId,
+            amount: Balance,
+            data: Vec<u8>,
+        ) -> Result<(), Error> {
+            if amount > self.max_flash_loan() {
+                return Err(Error::MaxFlashLoanExceeded);
+            }
+
+            let initiator = self.env().caller();
+            let token = self.env().account_id();
+            let fee = self.flash_fee(amount);
+            self._mint(receiver, amount)?;
+
+            let selector = ink::selector_bytes!("ERC3156FlashBorrower::on_flash_loan");
+            self._call_and_check(
+                receiver,
+                selector,
+                ExecutionInput::new(Selector::new(selector))
+                    .push_arg(initiator)
+                    .push_arg(token)
+                    .push_arg(amount)
+                    .push_arg(fee)
+                    .push_arg(data),
+            )?;
+
+            let repayment = amount.checked_add(fee).ok_or(Error::Overflow)?;
+            self._spend_allowance(receiver, token, repayment)?;
+            match self.flash_fee_receiver {
+                Some(fee_receiver) if fee > 0 => {
+                    self._burn(receiver, amount)?;
+                    self._transfer_without_fee(Some(receiver), Some(fee_receiver), fee)
+                }
+                _ => self._burn(receiver, repayment),
+            }
+        }
+
+        #[ink(message)]
+        pub fn mint(
+            &mut self,
+            value: Balance,
+        ) -> core::result::Result<(), Error> {
+            let caller = self.env().caller();
+            self._check_role(MINTER)?;
+
+            self._mint(caller, value)
+        }
+
+        #[ink(message)]
+        pub fn mint_to(&mut self, to: AccountId, value: Balance) -> Result<(), Error> {
+            self._check_role(MINTER)?;
+
+            self._mint(to, value)
+        }
+
+        /// Mints to every recipient at once; any failing entry reverts the whole batch.
+        #[ink(message)]
+        pub fn mint_batch(&mut self, recipients: Vec<(AccountId, Balance)>) -> Result<(), Error> {
+            self._check_role(MINTER)?;
+
+            let total = Self::_total_of(&recipients)?;
+            self._increase_supply(total)?;
+            for (to, value) in recipients {
+                self._transfer(None, Some(to), value)?;
+            }
+
+            Ok(())
+        }
+
+        /// Transfers to every recipient at once; any failing entry reverts the whole batch.
+        #[ink(message)]
+        pub fn transfer_batch(
+            &mut self,
+            transfers: Vec<(AccountId, Balance)>,
+        ) -> Result<(), Error> {
+            let from = self.env().caller();
+            if self._balance_of(from) < Self::_total_of(&transfers)? {
+                return Err(Error::InsufficientBalance);
+            }
+
+            for (to, value) in transfers {
+                self._do_safe_transfer_check(from, from, to, value, Vec::new())?;
+                self._transfer(Some(from), Some(to), value)?;
+            }
+
+            Ok(())
+        }
+
+        /// Like `transfer_batch`, spending the caller's allowance once for the total.
+        #[ink(message)]
+        pub fn transfer_from_batch(
+            &mut self,
+            from: AccountId,
+            transfers: Vec<(AccountId, Balance)>,
+        ) -> Result<(), Error> {
+            let caller = self.env().caller();
+            let total = Self::_total_of(&transfers)?;
+            self._spend_allowance(from, caller, total)?;
+            if self._balance_of(from) < total {
+                return Err(Error::InsufficientBalance);
+            }
+
+            for (to, value) in transfers {
+                self._do_safe_transfer_check(caller, from, to, value, Vec::new())?;
+                self._transfer(Some(from), Some(to), value)?;
+            }
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn campaign(&self, campaign_id: CampaignId) -> Option<AirdropCampaign> {
+            self.campaigns.get(campaign_id)
+        }
+
+        #[ink(message)]
+        pub fn is_claimed(&self, campaign_id: CampaignId, index: u32) -> bool {
+            let word = self.claimed_bitmap.get((campaign_id, index / 128)).unwrap_or_default();
+            word & (1 << (index % 128)) != 0
+        }
+
+        /// Registers an airdrop, moving `amount` from the caller's balance into the contract.
+        #[ink(message)]
+        pub fn create_airdrop(
+            &mut self,
+            merkle_root: [u8; 32],
+            amount: Balance,
+            expires_at: Timestamp,
+        ) -> Result<CampaignId, Error> {
+            self._check_not_reentrant()?;
+            self._check_role(ADMIN)?;
+            let caller = self.env().caller();
+            self._transfer(Some(caller), Some(self.env().account_id()), amount)?;
+
+            let campaign_id = self.next_campaign_id;
+            self.next_campaign_id = campaign_id.checked_add(1).ok_or(Error::Overflow)?;
+            self.campaigns.insert(
+                campaign_id,
+                &AirdropCampaign {
+                    merkle_root,
+                    remaining: amount,
+                    expires_at,
+                },
+            );
+
+            self.env().emit_event(AirdropCreated {
+                campaign_id,
+                merkle_root,
+                amount,
+                expires_at,
+            });
+
+            Ok(campaign_id)
+        }
+
+        /// Claims the caller's `amount` at leaf `index` of a campaign.
+        #[ink(message)]
+        pub fn claim(
+            &mut self,
+            campaign_id: CampaignId,
+            index: u32,
+            amount: Balance,
+            proof: Vec<[u8; 32]>,
+        ) -> Result<(), Error> {
+            let mut campaign = self
+                .campaigns
+                .get(campaign_id)
+                .ok_or(Error::NonexistentCampaign)?;
+            if self.env().block_timestamp() >= campaign.expires_at {
+                return Err(Error::CampaignExpired);
+            }
+            if self.is_claimed(campaign_id, index) {
+                return Err(Error::AlreadyClaimed);
+            }
+
+            let account = self.env().caller();
+            let leaf = self.env().hash_encoded::<Blake2x256, _>(&(index, account, amount));
+            if !self._verify_proof(&proof, campaign.merkle_root, leaf) {
+                return Err(Error::InvalidProof);
+            }
+
+            campaign.remaining = campaign
+                .remaining
+                .checked_sub(amount)
+                .ok_or(Error::InsufficientBalance)?;
+            self.campaigns.insert(campaign_id, &campaign);
+            let word = self.claimed_bitmap.get((campaign_id, index / 128)).unwrap_or_default();
+            self.claimed_bitmap
+                .insert((campaign_id, index / 128), &(word | (1 << (index % 128))));
+            self._transfer(Some(self.env().account_id()), Some(account), amount)?;
+
+            self.env().emit_event(AirdropClaimed {
+                campaign_id,
+                account,
+                index,
+                amount,
+            });
+
+            Ok(())
+        }
+
+        /// Returns the unclaimed tokens of an expired campaign to the caller.
+        #[ink(message)]
+        pub fn sweep(&mut self, campaign_id: CampaignId) -> Result<(), Error> {
+            self._check_role(ADMIN)?;
+            let mut campaign = self
+                .campaigns
+                .get(campaign_id)
+                .ok_or(Error::NonexistentCampaign)?;
+            if self.env().block_timestamp() < campaign.expires_at {
+                return Err(Error::CampaignNotExpired);
+            }
+
+            let amount = campaign.remaining;
+            campaign.remaining = 0;
+            self.campaigns.insert(campaign_id, &campaign);
+            self._transfer(
+                Some(self.env().account_id()),
+                Some(self.env().caller()),
+                amount,
+            )?;
+
+            self.env().emit_event(AirdropSwept {
+                campaign_id,
+                amount,
+            });
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn vesting_schedule(&self, schedule_id: VestingId) -> Option<VestingSchedule> {
+            self.vesting_schedules.get(schedule_id)
+        }
+
+        /// Escrows `amount` from the caller, vesting linearly to `beneficiary`
+        /// over `duration` from `start`, with nothing vested before `start + cliff`.
+        #[ink(message)]
+        pub fn create_vesting(
+            &mut self,
+            beneficiary: AccountId,
+            amount: Balance,
+            start: Timestamp,
+            cliff: Timestamp,
+            duration: Timestamp,
+            revocable: bool,
+        ) -> Result<VestingId, Error> {
+            self._check_not_reentrant()?;
+            if duration == 0 || cliff > duration {
+                return Err(Error::InvalidSchedule);
+            }
+
+            let creator = self.env().caller();
+            self._transfer(Some(creator), Some(self.env().account_id()), amount)?;
+
+            let schedule_id = self.next_vesting_id;
+            self.next_vesting_id = schedule_id.checked_add(1).ok_or(Error::Overflow)?;
+            self.vesting_schedules.insert(
+                schedule_id,
+                &VestingSchedule {
+                    creator,
+                    beneficiary,
+                    amount,
+                    released: 0,
+                    start,
+                    cliff,
+                    duration,
+                    revocable,
+                    revoked: false,
+                },
+            );
+
+            self.env().emit_event(VestingCreated {
+                schedule_id,
+                beneficiary,
+                amount,
+            });
+
+            Ok(schedule_id)
+        }
+
+        #[ink(message)]
+        pub fn vested_amount(&self, schedule_id: VestingId) -> Result<Balance, Error> {
+            let schedule = self._vesting_schedule(schedule_id)?;
+
+            Ok(self._vested_amount(&schedule))
+        }
+
+        #[ink(message)]
+        pub fn releasable_amount(&self, schedule_id: VestingId) -> Result<Balance, Error> {
+            let schedule = self._vesting_schedule(schedule_id)?;
+
+            self._releasable_amount(&schedule)
+        }
+
+        /// Pays out everything vested so far to the beneficiary.
+        #[ink(message)]
+        pub fn release(&mut self, schedule_id: VestingId) -> Result<(), Error> {
+            let mut schedule = self._vesting_schedule(schedule_id)?;
+            let amount = self._releasable_amount(&schedule)?;
+            schedule.released = schedule.released.checked_add(amount).ok_or(Error::Overflow)?;
+            self.vesting_schedules.insert(schedule_id, &schedule);
+            self._transfer(
+                Some(self.env().account_id()),
+                Some(schedule.beneficiary),
+                amount,
+            )?;
+
+            self.env().emit_event(VestingReleased {
+                schedule_id,
+                beneficiary: schedule.beneficiary,
+                amount,
+            });
+
+            Ok(())
+        }
+
+        /// Stops a revocable schedule, refunding the unvested part to its creator.
+        #[ink(message)]
+        pub fn revoke(&mut self, schedule_id: VestingId) -> Result<(), Error> {
+            let mut schedule = self._vesting_schedule(schedule_id)?;
+            if schedule.creator != self.env().caller() {
+                return Err(Error::IllegalManager);
+            }
+            if !schedule.revocable || schedule.revoked {
+                return Err(Error::NotRevocable);
+            }
+
+            let vested = self._vested_amount(&schedule);
+            let refund = schedule.amount.checked_sub(vested).ok_or(Error::Underflow)?;
+            schedule.amount = vested;
+            schedule.revoked = true;
+            self.vesting_schedules.insert(schedule_id, &schedule);
+            self._transfer(
+                Some(self.env().account_id()),
+                Some(schedule.creator),
+                refund,
+            )?;
+
+            self.env().emit_event(VestingRevoked {
+                schedule_id,
+                refund,
+            });
+
+            Ok(())
+        }
+
+        /// Tokens of `who` that cannot move before their unlock time.
+        #[ink(message)]
+        pub fn locked_balance_of(&self, who: AccountId) -> Balance {
+            let now = self.env().block_timestamp();
+            self.locks
+                .get(who)
+                .unwrap_or_default()
+                .iter()
+                .filter(|(unlock_at, _)| *unlock_at > now)
+                .fold(0, |total: Balance, (_, amount)| total.saturating_add(*amount))
+        }
+
+        #[ink(message)]
+        pub fn transferable_balance_of(&self, who: AccountId) -> Balance {
+            self._balance_of(who).saturating_sub(self.locked_balance_of(who))
+        }
+
+        /// Makes `amount` of the caller's balance non-transferable until `unlock_at`.
+        #[ink(message)]
+        pub fn lock(&mut self, amount: Balance, unlock_at: Timestamp) -> Result<(), Error> {
+            let caller = self.env().caller();
+
+            self._lock(caller, amount, unlock_at)
+        }
+
+        #[ink(message)]
+        pub fn lock_for(
+            &mut self,
+            account: AccountId,
+            amount: Balance,
+            unlock_at: Timestamp,
+        ) -> Result<(), Error> {
+            self._check_role(ADMIN)?;
+
+            self._lock(account, amount, unlock_at)
+        }
+
+        #[ink(message)]
+        pub fn is_frozen(&self, account: AccountId) -> bool {
+            self.frozen.contains(account)
+        }
+
+        /// Blocks `account` from sending, receiving, spending and approving tokens.
+        #[ink(message)]
+        pub fn freeze(&mut self, account: AccountId) -> Result<(), Error> {
+            self._check_role(COMPLIANCE)?;
+            self.frozen.insert(account, &());
+
+            self.env().emit_event(AccountFrozen { account });
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn unfreeze(&mut self, account: AccountId) -> Result<(), Error> {
+            self._check_role(COMPLIANCE)?;
+            self.frozen.remove(account);
+
+            self.env().emit_event(AccountUnfrozen { account });
+
+            Ok(())
+        }
+
+        /// Pre-checks a transfer, returning `TRANSFER_OK` or a restriction code.
+        #[ink(message)]
+        pub fn detect_transfer_restriction(
+            &self,
+            from: AccountId,
+            to: AccountId,
+            value: Balance,
+        ) -> u8 {
+            match self._check_transfer_allowed(Some(from), Some(to), value) {
+                Ok(()) => TRANSFER_OK,
+                Err(error) => Self::_restriction_code(&error),
+            }
+        }
+
+        #[ink(message)]
+        pub fn message_for_transfer_restriction(&self, code: u8) -> String {
+            String::from(Self::_restriction_message(code))
+        }
+
+        #[ink(message)]
+        pub fn is_permissioned(&self) -> bool {
+            self.permissioned
+        }
+
+        #[ink(message)]
+        pub fn is_allowlisted(&self, account: AccountId) -> bool {
+            self.allowlist.contains(account)
+        }
+
+        #[ink(message)]
+        pub fn allowlist_add_batch(&mut self, accounts: Vec<AccountId>) -> Result<(), Error> {
+            self._check_role(KYC_OFFICER)?;
+            for account in accounts {
+                self._set_allowlisted(account, true);
+            }
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn allowlist_remove_batch(&mut self, accounts: Vec<AccountId>) -> Result<(), Error> {
+            self._check_role(KYC_OFFICER)?;
+            for account in accounts {
+                self._set_allowlisted(account, false);
+            }
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn transfer_fee(&self) -> u16 {
+            self.transfer_fee_bps
+        }
+
+        #[ink(message)]
+        pub fn max_transfer_fee(&self) -> u16 {
+            self.max_transfer_fee_bps
+        }
+
+        #[ink(message)]
+        pub fn fee_treasury(&self) -> Option<AccountId> {
+            self.fee_treasury
+        }
+
+        #[ink(message)]
+        pub fn is_fee_exempt(&self, account: AccountId) -> bool {
+            self.fee_exempt.contains(account)
+        }
+
+        /// What a recipient receives when `value` is transferred between non-exempt accounts.
+        #[ink(message)]
+        pub fn quote_transfer(&self, value: Balance) -> Balance {
+            value - Self::_bps_of(value, self.transfer_fee_bps)
+        }
+
+        /// Sets the transfer fee; without a treasury the fee is burned.
+        #[ink(message)]
+        pub fn set_transfer_fee(
+            &mut self,
+            fee_bps: u16,
+            treasury: Option<AccountId>,
+        ) -> Result<(), Error> {
+            self._check_not_reentrant()?;
+            self._check_role(ADMIN)?;
+            if fee_bps > self.max_transfer_fee_bps {
+                return Err(Error::InvalidFee);
+            }
+            if let Some(treasury) = treasury {
+                if self.is_frozen(treasury) {
+                    return Err(Error::AccountFrozen);
+                }
+                if self.permissioned && !self.is_allowlisted(treasury) {
+                    return Err(Error::NotAllowlisted);
+                }
+            }
+
+            self.transfer_fee_bps = fee_bps;
+            self.fee_treasury = treasury;
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn set_fee_exempt(&mut self, account: AccountId, exempt: bool) -> Result<(), Error> {
+            self._check_role(ADMIN)?;
+            if exempt {
+                self.fee_exempt.insert(account, &());
+            } else {
+                self.fee_exempt.remove(account);
+            }
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn is_clawback_enabled(&self) -> bool {
+            self.clawback_enabled
+        }
+
+        /// Moves tokens without an allowance, ignoring pauses, freezes and locks.
+        ///
+        /// `reason_hash` identifies the order behind the recovery.
+        #[ink(message)]
+        pub fn clawback(
+            &mut self,
+            from: AccountId,
+            to: AccountId,
+            value: Balance,
+            reason_hash: [u8; 32],
+        ) -> Result<(), Error> {
+            if !self.clawback_enabled {
+                return Err(Error::ClawbackDisabled);
+            }
+            self._check_role(CLAWBACK)?;
+            if self.permissioned && !self.is_allowlisted(to) {
+                return Err(Error::NotAllowlisted);
+            }
+            self._update(Some(from), Some(to), value)?;
+
+            self.env().emit_event(Clawback {
+                from,
+                to,
+                value,
+                reason_hash,
+            });
+
+            Ok(())
+        }
+
+        /// Destroys `value` of the caller's own tokens.
+        #[ink(message)]
+        pub fn burn(
+            &mut self,
+            value: Balance,
+        ) -> core::result::Result<(), Error> {
+            let caller = self.env().caller();
+
+            self._burn(caller, value)
+        }
+
+        /// Destroys `value` of `account`'s tokens, spending the caller's allowance.
+        #[ink(message)]
+        pub fn burn_from(&mut self, account: AccountId, value: Balance) -> Result<(), Error> {
+            let caller = self.env().caller();
+            self._spend_allowance(account, caller, value)?;
+
+            self._burn(account, value)
+        }
+
+        /// Destroys `value` of any `account`'s tokens without an allowance.
+        #[ink(message)]
+        pub fn burn_account(&mut self, account: AccountId, value: Balance) -> Result<(), Error> {
+            self._check_role(BURNER)?;
+
+            self._burn(account, value)
+        }
+
+        fn _check_owner(&self) -> Result<(), Error> {
+            if self.owner != Some(self.env().caller()) {
+                return Err(Error::IllegalManager);
+            }
+
+            Ok(())
+        }
+
+        /// Rejects root storage writes during a callback, which the calling message would
+        /// overwrite with its own copy of the root once the callback returns.
+        fn _check_not_reentrant(&self) -> Result<(), Error> {
+            if self.callback_active.get().unwrap_or_default() {
+                return Err(Error::ReentrantCall);
+            }
+
+            Ok(())
+        }
+
+        fn _check_role(&self, role: RoleId) -> Result<(), Error> {
+            if !self.has_role(role, self.env().caller()) {
+                return Err(Error::MissingRole(role));
+            }
+
+            Ok(())
+        }
+
+        fn _check_owner_or_role(&self, role: RoleId) -> Result<(), Error> {
+            if self.owner == Some(self.env().caller()) {
+                return Ok(());
+            }
+
+            self._check_role(role)
+        }
+
+        fn _grant_role(&mut self, role: RoleId, account: AccountId) {
+            if self.has_role(role, account) {
+                return;
+            }
+            self.roles.insert((role, account), &());
+
+            self.env().emit_event(RoleGranted {
+                role,
+                grantee: account,
+                grantor: self.env().caller(),
+            });
+        }
+
+        fn _revoke_role(&mut self, role: RoleId, account: AccountId) {
+            if !self.has_role(role, account) {
+                return;
+            }
+            self.roles.remove((role, account));
+
+            self.env().emit_event(RoleRevoked {
+                role,
+                account,
+                sender: self.env().caller(),
+            });
+        }
+
+        fn _set_owner(&mut self, new_owner: Option<AccountId>) {
+            let previous_owner = self.owner;
+            self.owner = new_owner;
+            self.pending_owner = None;
+            if let Some(previous_owner) = previous_owner {
+                for role in OWNER_ROLES {
+                    if !self.has_role(role, previous_owner) {
+                        continue;
+                    }
+                    self._revoke_role(role, previous_owner);
+                    if let Some(new_owner) = new_owner {
+                        self._grant_role(role, new_owner);
+                    }
+                }
+            }
+
+            self.env().emit_event(OwnershipTransferred {
+                previous_owner,
+                new_owner,
+            });
+        }
+
+        fn _balance_of(&self, who: AccountId) -> Balance {
+            self.balances.get(who).unwrap_or_default()
+        }
+
+        fn _allowance(&self, owner: AccountId, spender: AccountId) -> Balance {
+            self.approval.get((owner, spender)).unwrap_or_default()
+        }
+
+        fn _approve(
+            &mut self,
+            owner: AccountId,
+            spender: AccountId,
+            value: Balance,
+        ) -> Result<(), Error> {
+            if self.is_frozen(owner) || self.is_frozen(spender) {
+                return Err(Error::AccountFrozen);
+            }
+
+            self.approval.insert((owner, spender), &value);
+
+            self.env().emit_event(Approval {
+                owner,
+                spender,
+                value,
+            });
+
+            Ok(())
+        }
+
+        fn _mint(&mut self, to: AccountId, value: Balance) -> Result<(), Error> {
+            self._increase_supply(value)?;
+            self._transfer(None, Some(to), value)
+        }
+
+        fn _burn(&mut self, from: AccountId, value: Balance) -> Result<(), Error> {
+            self._transfer(Some(from), None, value)?;
+            self._decrease_supply(value)
+        }
+
+        /// Every minting path grows the supply here, so the cap is enforced in one place.
+        fn _increase_supply(&mut self, value: Balance) -> Result<(), Error> {
+            self._check_not_reentrant()?;
+            self._update_total_supply_snapshot();
+            let total_supply = self.total_supply.checked_add(value).ok_or(Error::Overflow)?;
+            if let Some(cap) = self.cap {
+                if total_supply > cap {
+                    return Err(Error::CapExceeded);
+                }
+            }
+            self.total_supply = total_supply;
+            self._write_total_supply_checkpoint();
+
+            Ok(())
+        }
+
+        fn _decrease_supply(&mut self, value: Balance) -> Result<(), Error> {
+            self._check_not_reentrant()?;
+            self._update_total_supply_snapshot();
+            self.total_supply = self.total_supply.checked_sub(value).ok_or(Error::Underflow)?;
+            self._write_total_supply_checkpoint();
+
+            Ok(())
+        }
+
+        fn _total_of(entries: &[(AccountId, Balance)]) -> Result<Balance, Error> {
+            entries
+                .iter()
+                .try_fold(0, |total: Balance, (_, value)| total.checked_add(*value))
+                .ok_or(Error::Overflow)
+        }
+
+        /// Adds a lock, merging it into one with the same unlock time and dropping the ones
+        /// that have already expired.
+        fn _lock(
+            &mut self,
+            account: AccountId,
+            amount: Balance,
+            unlock_at: Timestamp,
+        ) -> Result<(), Error> {
+            if amount > self.transferable_balance_of(account) {
+                return Err(Error::InsufficientBalance);
+            }
+
+            let now = self.env().block_timestamp();
+            let mut locks = self.locks.get(account).unwrap_or_default();
+            locks.retain(|(unlock_at, _)| *unlock_at > now);
+            if let Some((_, locked)) = locks.iter_mut().find(|(at, _)| *at == unlock_at) {
+                *locked = locked.checked_add(amount).ok_or(Error::Overflow)?;
+            } else if locks.len() >= MAX_LOCKS {
+                return Err(Error::TooManyLocks);
+            } else {
+                locks.push((unlock_at, amount));
+            }
+            self.locks.insert(account, &locks);
+
+            self.env().emit_event(TokensLocked {
+                account,
+                amount,
+                unlock_at,
+            });
+
+            Ok(())
+        }
+
+        /// Drops the expired locks of `account`, writing only when one has expired.
+        fn _prune_locks(&mut self, account: AccountId) {
+            let mut locks = match self.locks.get(account) {
+                Some(locks) => locks,
+                None => return,
+            };
+            let now = self.env().block_timestamp();
+            let count = locks.len();
+            locks.retain(|(unlock_at, _)| *unlock_at > now);
+            if locks.is_empty() {
+                self.locks.remove(account);
+            } else if locks.len() < count {
+                self.locks.insert(account, &locks);
+            }
+        }
+
+        fn _vesting_schedule(&self, schedule_id: VestingId) -> Result<VestingSchedule, Error> {
+            self.vesting_schedules
+                .get(schedule_id)
+                .ok_or(Error::NonexistentVesting)
+        }
+
+        fn _vested_amount(&self, schedule: &VestingSchedule) -> Balance {
+            let elapsed = self.env().block_timestamp().saturating_sub(schedule.start);
+            if schedule.revoked || elapsed >= schedule.duration {
+                return schedule.amount;
+            }
+            if elapsed < schedule.cliff {
+                return 0;
+            }
+
+            let elapsed = Balance::from(elapsed);
+            let duration = Balance::from(schedule.duration);
+            schedule.amount / duration * elapsed + schedule.amount % duration * elapsed / duration
+        }
+
+        fn _releasable_amount(&self, schedule: &VestingSchedule) -> Result<Balance, Error> {
+            self._vested_amount(schedule)
+                .checked_sub(schedule.released)
+                .ok_or(Error::Underflow)
+        }
+
+        /// Folds `proof` into `leaf`, hashing each pair in sorted order.
+        fn _verify_proof(&self, proof: &[[u8; 32]], root: [u8; 32], leaf: [u8; 32]) -> bool {
+            let computed = proof.iter().fold(leaf, |hash, sibling| {
+                let pair = if hash <= *sibling {
+                    (hash, *sibling)
+                } else {
+                    (*sibling, hash)
+                };
+                self.env().hash_encoded::<Blake2x256, _>(&pair)
+            });
+
+            computed == root
+        }
+
+        /// `bps` basis points of `value`, rounded down.
+        fn _bps_of(value: Balance, bps: u16) -> Balance {
+            let bps = Balance::from(bps);
+            let denominator = Balance::from(MAX_BPS);
+
+            value / denominator * bps + value % denominator * bps / denominator
+        }
+
+        /// Returns the first index in `0..len` for which `pred` is false, given that `pred`
+        /// holds for a prefix of the indices.
+        fn _partition_point(len: u32, mut pred: impl FnMut(u32) -> bool) -> u32 {
+            let (mut low, mut high) = (0, len);
+            while low < high {
+                let mid = low + (high - low) / 2;
+                if pred(mid) {
+                    low = mid + 1;
+                } else {
+                    high = mid;
+                }
+            }
+
+            low
+        }
+
+        fn _snapshot(&self, key: Option<AccountId>, index: u32) -> (SnapshotId, Balance) {
+            self.snapshots.get((key, index)).unwrap_or_default()
+        }
+
+        /// Looks up the value recorded for `snapshot_id`, or `None` if it has not changed since.
+        fn _value_at(
+            &self,
+            key: Option<AccountId>,
+            snapshot_id: SnapshotId,
+        ) -> Result<Option<Balance>, Error> {
+            if snapshot_id == 0 || snapshot_id > self.snapshot_id {
+                return Err(Error::NonexistentSnapshot);
+            }
+
+            let len = self.snapshot_counts.get(key).unwrap_or_default();
+            let index =
+                Self::_partition_point(len, |index| self._snapshot(key, index).0 < snapshot_id);
+            Ok((index < len).then(|| self._snapshot(key, index).1))
+        }
+
+        /// Keeps the pre-change value the first time it changes after a snapshot.
+        fn _update_snapshot(&mut self, key: Option<AccountId>, value: Balance) {
+            let snapshot_id = self.snapshot_id;
+            let len = self.snapshot_counts.get(key).unwrap_or_default();
+            let recorded = len
+                .checked_sub(1)
+                .is_some_and(|last| self._snapshot(key, last).0 >= snapshot_id);
+            if snapshot_id == 0 || recorded {
+                return;
+            }
+
+            self.snapshots.insert((key, len), &(snapshot_id, value));
+            self.snapshot_counts.insert(key, &(len + 1));
+        }
+
+        fn _update_account_snapshot(&mut self, account: AccountId) {
+            self._update_snapshot(Some(account), self._balance_of(account));
+        }
+
+        fn _update_total_supply_snapshot(&mut self) {
+            self._update_snapshot(None, self.total_supply);
+        }
+
+        fn _checkpoint(&self, key: Option<AccountId>, index: u32) -> (BlockNumber, Balance) {
+            self.checkpoints.get((key, index)).unwrap_or_default()
+        }
+
+        fn _checkpoint_at(
+            &self,
+            key: Option<AccountId>,
+            block_number: BlockNumber,
+        ) -> Result<Balance, Error> {
+            if block_number >= self.env().block_number() {
+                return Err(Error::FutureLookup);
+            }
+
+            let len = self.checkpoint_counts.get(key).unwrap_or_default();
+            let index =
+                Self::_partition_point(len, |index| self._checkpoint(key, index).0 <= block_number);
+            Ok(index
+                .checked_sub(1)
+                .map(|index| self._checkpoint(key, index).1)
+                .unwrap_or_default())
+        }
+
+        /// Records `value` for the current block, replacing an entry from earlier in the block.
+        fn _write_checkpoint(&mut self, key: Option<AccountId>, value: Balance) {
+            let block_number = self.env().block_number();
+            let len = self.checkpoint_counts.get(key).unwrap_or_default();
+            match len.checked_sub(1) {
+                Some(last) if self._checkpoint(key, last).0 == block_number => {
+                    self.checkpoints.insert((key, last), &(block_number, value));
+                }
+                _ => {
+                    self.checkpoints.insert((key, len), &(block_number, value));
+                    self.checkpoint_counts.insert(key, &(len + 1));
+                }
+            }
+        }
+
+        fn _write_total_supply_checkpoint(&mut self) {
+            self._write_checkpoint(None, self.total_supply);
+        }
+
+        fn _move_voting_power(
+            &mut self,
+            from: Option<AccountId>,
+            to: Option<AccountId>,
+            value: Balance,
+        ) -> Result<(), Error> {
+            if from == to || value == 0 {
+                return Ok(());
+            }
+
+            if let Some(from) = from {
+                let previous_balance = self.get_votes(from);
+                let new_balance = previous_balance.checked_sub(value).ok_or(Error::Underflow)?;
+                self._write_votes(from, previous_balance, new_balance);
+            }
+            if let Some(to) = to {
+                let previous_balance = self.get_votes(to);
+                let new_balance = previous_balance.checked_add(value).ok_or(Error::Overflow)?;
+                self._write_votes(to, previous_balance, new_balance);
+            }
+
+            Ok(())
+        }
+
+        fn _write_votes(
+            &mut self,
+            delegate: AccountId,
+            previous_balance: Balance,
+            new_balance: Balance,
+        ) {
+            self._write_checkpoint(Some(delegate), new_balance);
+
+            self.env().emit_event(DelegateVotesChanged {
+                delegate,
+                previous_balance,
+                new_balance,
+            });
+        }
+
+        /// Lets a contract recipient accept or reject tokens through `PSP22Receiver`.
+        fn _do_safe_transfer_check(
+            &self,
+            operator: AccountId,
+            from: AccountId,
+            to: AccountId,
+            value: Balance,
+            data: Vec<u8>,
+        ) -> Result<(), Error> {
+            if !self.env().is_contract(&to) {
+                return Ok(());
+            }
+
+            let received = value - self._transfer_fee(Some(from), Some(to), value);
+            let result = build_call::<Environment>()
+                .call(to)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(ink::selector_bytes!(
+                        "PSP22Receiver::before_received"
+                    )))
+                    .push_arg(operator)
+                    .push_arg(from)
+                    .push_arg(received)
+                    .push_arg(data),
+                )
+                .returns::<Result<(), PSP22ReceiverError>>()
+                .try_invoke();
+
+            match result {
+                Ok(Ok(Ok(()))) => Ok(()),
+                Ok(Ok(Err(PSP22ReceiverError::TransferRejected(reason)))) => {
+                    Err(Error::SafeTransferCheckFailed(reason))
+                }
+                Ok(Err(error)) => Err(Error::SafeTransferCheckFailed(format!(
+                    "PSP22Receiver not implemented: {:?}",
+                    error
+                ))),
+                Err(error) => Err(Error::SafeTransferCheckFailed(format!("{:?}", error))),
+            }
+        }
+
+        /// Calls `target` and requires it to answer with `selector` as magic value.
+        ///
+        /// The target may call back into the token; `callback_active` keeps such calls
+        /// from writing root storage until it returns.
+        fn _call_and_check<Args: scale::Encode>(
+            &mut self,
+            target: AccountId,
+            selector: [u8; 4],
+            input: ExecutionInput<Args>,
+        ) -> Result<(), Error> {
+            if !self.env().is_contract(&target) {
+                return Err(Error::CallbackFailed(String::from("target is not a contract")));
+            }
+
+            let outer_callback = self.callback_active.get().unwrap_or_default();
+            self.callback_active.set(&true);
+            let result = build_call::<Environment>()
+                .call(target)
+                .call_flags(CallFlags::default().set_allow_reentry(true))
+                .exec_input(input)
+                .returns::<[u8; 4]>()
+                .try_invoke();
+            self.callback_active.set(&outer_callback);
+
+            match result {
+                Ok(Ok(magic_value)) if magic_value == selector => Ok(()),
+                Ok(Ok(_)) => Err(Error::CallbackFailed(String::from("wrong magic value"))),
+                Ok(Err(error)) => Err(Error::CallbackFailed(format!("{:?}", error))),
+                Err(error) => Err(Error::CallbackFailed(format!("{:?}", error))),
+            }
+        }
+
+        fn _spend_allowance(
+            &mut self,
+            owner: AccountId,
+            spender: AccountId,
+            value: Balance,
+        ) -> Result<(), Error> {
+            let approval = self
+                ._allowance(owner, spender)
+                .checked_sub(value)
+                .ok_or(Error::InsufficientApproval)?;
+            self._approve(owner, spender, approval)
+        }
+
+        /// Moves `value` from `from` to `to`.
+        ///
+        /// `None` as `from` credits freshly minted tokens and `None` as `to`
+        /// destroys them; `total_supply` is adjusted by the caller through
+        /// `_increase_supply` and `_decrease_supply`.
+        pub fn _transfer(
+            &mut self,
+            from: Option<AccountId>,
+            to: Option<AccountId>,
+            value: Balance,
+        ) -> Result<(), Error> {
+            self._check_transfer_allowed(from, to, value)?;
+            if let Some(from) = from {
+                self._prune_locks(from);
+            }
+
+            let fee = self._transfer_fee(from, to, value);
+            if let Some(payer) = from.filter(|_| fee > 0) {
+                let treasury = self.fee_treasury;
+                self._update(Some(payer), treasury, fee)?;
+                if treasury.is_none() {
+                    self._decrease_supply(fee)?;
+                }
+
+                self.env().emit_event(FeeCharged {
+                    from: payer,
+                    treasury,
+                    fee,
+                });
+            }
+
+            self._update(from, to, value - fee)
+        }
+
+        /// Moves `value` under the same account checks as `_transfer`, without charging the
+        /// transfer fee.
+        fn _transfer_without_fee(
+            &mut self,
+            from: Option<AccountId>,
+            to: Option<AccountId>,
+            value: Balance,
+        ) -> Result<(), Error> {
+            self._check_movement_allowed(from, to, value)?;
+            self._update(from, to, value)
+        }
+
+        /// The fee on moving `value` between two accounts; mints, burns and
+        /// movements touching a fee-exempt account are free.
+        fn _transfer_fee(
+            &self,
+            from: Option<AccountId>,
+            to: Option<AccountId>,
+            value: Balance,
+        ) -> Balance {
+            match (from, to) {
+                (Some(from), Some(to)) if !self.is_fee_exempt(from) && !self.is_fee_exempt(to) => {
+                    Self::_bps_of(value, self.transfer_fee_bps)
+                }
+                _ => 0,
+            }
+        }
+
+        fn _set_allowlisted(&mut self, account: AccountId, allowlisted: bool) {
+            if self.is_allowlisted(account) == allowlisted {
+                return;
+            }
+
+            if allowlisted {
+                self.allowlist.insert(account, &());
+                self.env().emit_event(AllowlistAdded { account });
+            } else {
+                self.allowlist.remove(account);
+                self.env().emit_event(AllowlistRemoved { account });
+            }
+        }
+
+        fn _restriction_code(error: &Error) -> u8 {
+            match error {
+                Error::Paused => RESTRICTION_PAUSED,
+                Error::AccountFrozen => RESTRICTION_ACCOUNT_FROZEN,
+                Error::InsufficientBalance => RESTRICTION_INSUFFICIENT_BALANCE,
+                Error::BalanceLocked => RESTRICTION_BALANCE_LOCKED,
+                Error::NotAllowlisted => RESTRICTION_NOT_ALLOWLISTED,
+                _ => RESTRICTION_UNKNOWN,
+            }
+        }
+
+        fn _restriction_message(code: u8) -> &'static str {
+            match code {
+                TRANSFER_OK => "No restriction",
+                RESTRICTION_PAUSED => "Token transfers are paused",
+                RESTRICTION_ACCOUNT_FROZEN => "Sender, recipient or fee treasury is frozen",
+                RESTRICTION_INSUFFICIENT_BALANCE => "Sender has insufficient balance",
+                RESTRICTION_BALANCE_LOCKED => "Sender balance is locked",
+                RESTRICTION_NOT_ALLOWLISTED => "Recipient or fee treasury is not on the allowlist",
+                _ => "Unknown restriction",
+            }
+        }
+
+        /// Runs the `detect_transfer_restriction` checks, failing with the restriction code.
+        fn _check_transfer_restriction(
+            &self,
+            from: AccountId,
+            to: AccountId,
+            value: Balance,
+        ) -> Result<(), Error> {
+            self._check_transfer_allowed(Some(from), Some(to), value)
+                .map_err(|error| Error::TransferRestricted(Self::_restriction_code(&error)))
+        }
+
+        /// Runs `_check_movement_allowed`, plus the treasury's receipt of any transfer fee.
+        fn _check_transfer_allowed(
+            &self,
+            from: Option<AccountId>,
+            to: Option<AccountId>,
+            value: Balance,
+        ) -> Result<(), Error> {
+            self._check_movement_allowed(from, to, value)?;
+            match self.fee_treasury {
+                Some(treasury) if self._transfer_fee(from, to, value) > 0 => {
+                    self._check_movement_allowed(None, Some(treasury), 0)
+                }
+                _ => Ok(()),
+            }
+        }
+
+        /// Rejects movements that pausing, freezing, the allowlist, balances or locks forbid.
+        fn _check_movement_allowed(
+            &self,
+            from: Option<AccountId>,
+            to: Option<AccountId>,
+            value: Balance,
+        ) -> Result<(), Error> {
+            if self.paused {
+                return Err(Error::Paused);
+            }
+            if from.is_some_and(|from| self.is_frozen(from))
+                || to.is_some_and(|to| self.is_frozen(to))
+            {
+                return Err(Error::AccountFrozen);
+            }
+            if self.permissioned && to.is_some_and(|to| !self.is_allowlisted(to)) {
+                return Err(Error::NotAllowlisted);
+            }
+            if let Some(from) = from {
+                if value > self._balance_of(from) {
+                    return Err(Error::InsufficientBalance);
+                }
+                if value > self.transferable_balance_of(from) {
+                    return Err(Error::BalanceLocked);
+                }
+            }
+
+            Ok(())
+        }
+
+        /// Updates balances, snapshots and votes without any policy checks.
+        /// All checks happen before any write.
+        fn _update(
+            &mut self,
+            from: Option<AccountId>,
+            to: Option<AccountId>,
+            value: Balance,
+        ) -> Result<(), Error> {
+            let from_balance = match from {
+                Some(from) => Some(
+                    self._balance_of(from)
+                        .checked_sub(value)
+                        .ok_or(Error::InsufficientBalance)?,
+                ),
+                None => None,
+            };
+
+            let to_balance = match to {
+                Some(to) if Some(to) == from => from_balance.map(|balance| balance + value),
+                Some(to) => Some(
+                    self._balance_of(to)
+                        .checked_add(value)
+                        .ok_or(Error::Overflow)?,
+                ),
+                None => None,
+            };
+
+            if let (Some(from), Some(balance)) = (from, from_balance) {
+                self._update_account_snapshot(from);
+                self.balances.insert(from, &balance);
+            }
+            if let (Some(to), Some(balance)) = (to, to_balance) {
+                self._update_account_snapshot(to);
+                self.balances.insert(to, &balance);
+            }
+
+            self._move_voting_power(
+                from.and_then(|from| self.delegates(from)),
+                to.and_then(|to| self.delegates(to)),
+                value,
+            )?;
+
+            self.env().emit_event(Transfer {
+                from,
+                to,
+                value,
+            });
+
+            Ok(())
+        }
+    }
+
+    impl PSP22 for Wasmerc20 {
+        #[ink(message)]
+        fn total_supply(&self) -> Balance {
+            self.total_supply
+        }
+
+        #[ink(message)]
+        fn balance_of(&self, owner: AccountId) -> Balance {
+            self._balance_of(owner)
+        }
+
+        #[ink(message)]
+        fn allowance(&self, owner: AccountId, spender: AccountId) -> Balance {
+            self._allowance(owner, spender)
+        }
+
+        #[ink(message)]
+        fn transfer(
+            &mut self,
+            to: AccountId,
+            value: Balance,
+            data: Vec<u8>,
+        ) -> Result<(), PSP22Error> {
+            let from = self.env().caller();
+            self._check_transfer_restriction(from, to, value)?;
+            self._do_safe_transfer_check(from, from, to, value, data)?;
+            self._transfer(Some(from), Some(to), value)?;
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn transfer_from(
+            &mut self,
+            from: AccountId,
+            to: AccountId,
+            value: Balance,
+            data: Vec<u8>,
+        ) -> Result<(), PSP22Error> {
+            let caller = self.env().caller();
+            self._check_transfer_restriction(from, to, value)?;
+            self._spend_allowance(from, caller, value)?;
+            self._do_safe_transfer_check(caller, from, to, value, data)?;
+
+            self._transfer(Some(from), Some(to), value)?;
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn approve(&mut self, spender: AccountId, value: Balance) -> Result<(), PSP22Error> {
+            let owner = self.env().caller();
+            self._approve(owner, spender, value)?;
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn increase_allowance(
+            &mut self,
+            spender: AccountId,
+            delta_value: Balance,
+        ) -> Result<(), PSP22Error> {
+            let owner = self.env().caller();
+            let allowance = self
+                ._allowance(owner, spender)
+                .checked_add(delta_value)
+                .ok_or(Error::Overflow)?;
+            self._approve(owner, spender, allowance)?;
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn decrease_allowance(
+            &mut self,
+            spender: AccountId,
+            delta_value: Balance,
+        ) -> Result<(), PSP22Error> {
+            let owner = self.env().caller();
+            let allowance = self
+                ._allowance(owner, spender)
+                .checked_sub(delta_value)
+                .ok_or(Error::AllowanceBelowZero)?;
+            self._approve(owner, spender, allowance)?;
+
+            Ok(())
+        }
+    }
+
+    impl PSP22Metadata for Wasmerc20 {
+        #[ink(message)]
+        fn token_name(&self) -> Option<String> {
+            self.name.clone()
+        }
+
+        #[ink(message)]
+        fn token_symbol(&self) -> Option<String> {
+            self.symbol.clone()
+        }
+
+        #[ink(message)]
+        fn token_decimals(&self) -> u8 {
+            self.decimals
+        }
+    }
 }